@@ -7,12 +7,14 @@ use std::{
 
 use anyhow::{bail, Result};
 use async_channel::Receiver;
-use serde::Deserialize;
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
 use slang_solidity::{
+    cursor::Cursor,
     kinds::{NonterminalKind, TerminalKind},
     language::Language,
-    query::Query,
-    text_index::TextRangeExtensions as _,
+    query::{Query, QueryMatch},
+    text_index::{TextRange, TextRangeExtensions as _},
 };
 use walkdir::WalkDir;
 
@@ -31,8 +33,114 @@ struct FoundryConfig {
     profile: Option<FoundryProfiles>,
 }
 
+#[derive(Parser)]
+struct Cli {
+    /// Output format for the collected findings
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// One line per finding on stderr, as the linter has always printed
+    Human,
+    /// A SARIF 2.1.0 JSON report on stdout, for CI to ingest
+    Sarif,
+}
+
+/// The severity of a lint finding. Only `Error` findings cause the process to exit non-zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single lint finding, independent of how it's ultimately rendered.
+#[derive(Debug, Clone)]
+struct Finding {
+    rule_id: &'static str,
+    severity: Severity,
+    file: PathBuf,
+    byte_range: (usize, usize),
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+/// A storage-access lint rule: a set of tree-sitter-style queries plus the metadata needed to
+/// report whatever they match. Each rule owns its own queries, capture names and ancestor
+/// checks in `classify`. The per-function traversal in `parse_and_lint` is shared by every
+/// rule and only reports a name that `classify` returns more than once within the same
+/// function, always with a "uses `...` more than once" message; a rule that flags a single
+/// access (e.g. an `s.` access inside a loop body) or a read followed by a write cannot be
+/// expressed through this struct alone.
+struct Rule {
+    id: &'static str,
+    severity: Severity,
+    queries: fn() -> Result<Vec<Query>>,
+    /// Given a raw query match, decides whether it represents a storage access worth
+    /// tracking. Returns the canonical name to dedupe on, together with the cursor to start
+    /// walking up from in search of the enclosing function, or `None` to skip this match.
+    classify: fn(&QueryMatch) -> Option<(String, Cursor)>,
+}
+
+/// Flags a function that reads the same `s.<member>` (or `_`-prefixed) identifier more than
+/// once, which could instead be cached in a local variable.
+const DUPLICATE_STORAGE_READ: Rule = Rule {
+    id: "duplicate-storage-read",
+    severity: Severity::Error,
+    queries: || {
+        Ok(vec![
+            Query::parse(
+                r#"
+                [MemberAccessExpression
+                    [Expression ["s"]]
+                    [Period]
+                    [MemberAccess
+                        @var_name [Identifier]
+                    ]
+                    ...
+                ]
+                "#,
+            )?,
+            Query::parse(
+                r#"
+                @var_name [Identifier]
+                "#,
+            )?,
+        ])
+    },
+    classify: |m| {
+        let var_cursor = m.captures.get("var_name")?.first()?;
+        let identifier = var_cursor.node().unparse();
+
+        if m.query_number == 0 {
+            return Some((format!("s.{identifier}"), var_cursor.clone()));
+        }
+
+        let mut parent_cursor = var_cursor.clone();
+        let is_member_access = parent_cursor.go_to_parent()
+            && parent_cursor
+                .node()
+                .is_nonterminal_with_kind(NonterminalKind::MemberAccess);
+        let is_call = parent_cursor.go_to_parent()
+            && parent_cursor
+                .node()
+                .is_nonterminal_with_kind(NonterminalKind::FunctionCallExpression);
+        if is_member_access || is_call || !identifier.starts_with('_') {
+            return None;
+        }
+        Some((identifier, var_cursor.clone()))
+    },
+};
+
+const RULES: &[Rule] = &[DUPLICATE_STORAGE_READ];
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     let mut solidity_version = "0.8.26".to_string();
     let config_file = PathBuf::from("foundry.toml");
     if fs::metadata(&config_file).is_ok() {
@@ -46,10 +154,10 @@ async fn main() -> Result<()> {
             solidity_version = solc_version;
         }
     }
-    println!("{solidity_version}");
+    eprintln!("{solidity_version}");
 
     let n_threads = available_parallelism()?.get();
-    println!("using {n_threads} threads");
+    eprintln!("using {n_threads} threads");
 
     let (tx, rx) = async_channel::unbounded();
 
@@ -58,10 +166,7 @@ async fn main() -> Result<()> {
             tokio::spawn({
                 let rx = rx.clone();
                 let solidity_version = solidity_version.clone();
-                async move {
-                    worker(rx, solidity_version).await?;
-                    Ok::<(), anyhow::Error>(())
-                }
+                async move { worker(rx, solidity_version).await }
             })
         })
         .collect();
@@ -93,30 +198,40 @@ async fn main() -> Result<()> {
     }
     tx.close();
 
-    let mut results = Vec::with_capacity(handles.len());
+    let mut findings = Vec::new();
     for handle in handles {
-        results.push(handle.await??);
+        findings.extend(handle.await??);
+    }
+
+    match cli.format {
+        OutputFormat::Human => report_human(&findings),
+        OutputFormat::Sarif => report_sarif(&findings)?,
+    }
+
+    if findings.iter().any(|f| f.severity == Severity::Error) {
+        std::process::exit(1);
     }
 
     Ok(())
 }
 
-async fn worker(rx: Receiver<PathBuf>, solidity_version: String) -> Result<()> {
+async fn worker(rx: Receiver<PathBuf>, solidity_version: String) -> Result<Vec<Finding>> {
     let language = Language::new(solidity_version.parse()?)?;
+    let mut findings = Vec::new();
     while let Ok(path) = rx.recv().await {
-        println!("processing {path:?}");
+        eprintln!("processing {path:?}");
         match parse_and_lint(&language, &path) {
-            Ok(()) => {}
+            Ok(file_findings) => findings.extend(file_findings),
             Err(e) => {
                 eprintln!("Error parsing {path:?}: {e:?}");
                 continue;
             }
         };
     }
-    Ok(())
+    Ok(findings)
 }
 
-fn parse_and_lint(lang: &Language, path: impl AsRef<Path>) -> Result<()> {
+fn parse_and_lint(lang: &Language, path: impl AsRef<Path>) -> Result<Vec<Finding>> {
     let path = path.as_ref();
     let contents = fs::read_to_string(path)?;
     let parse_output = lang.parse(NonterminalKind::SourceUnit, &contents);
@@ -130,80 +245,198 @@ fn parse_and_lint(lang: &Language, path: impl AsRef<Path>) -> Result<()> {
     if !parse_output.is_valid() {
         bail!("Parse error(s) found in {path:?}")
     }
-    let cursor = parse_output.create_tree_cursor();
-    let storage_struct_query = Query::parse(
-        r#"
-        [MemberAccessExpression
-            [Expression ["s"]]
-            [Period]
-            [MemberAccess
-                @var_name [Identifier]
-            ]
-            ...
-        ]
-        "#,
-    )?;
-    let normal_storage_query = Query::parse(
-        r#"
-        @var_name [Identifier]
-        "#,
-    )?;
-    // mapping of function identifier offset to a list of accessed members
-    let mut accesses = HashMap::<usize, Vec<String>>::new();
-    for m in cursor.query(vec![storage_struct_query, normal_storage_query]) {
-        let index = m.query_number;
-        let captures = m.captures;
-        let cursors = captures.get("var_name").unwrap();
-        let cursor = cursors.first().unwrap();
-        let mut member_name = cursor.node().unparse();
-        if index == 0 {
-            member_name = format!("s.{member_name}");
-        } else {
-            let mut parent_cursor = cursor.clone();
-            if parent_cursor.go_to_parent()
-                && parent_cursor
-                    .node()
-                    .is_nonterminal_with_kind(NonterminalKind::MemberAccess)
-            {
-                continue;
-            }
-            if parent_cursor.go_to_parent()
-                && parent_cursor
-                    .node()
-                    .is_nonterminal_with_kind(NonterminalKind::FunctionCallExpression)
-            {
-                continue;
-            }
-            if !member_name.starts_with('_') {
-                continue;
-            }
-        }
 
-        let mut function_cursor = cursor.clone();
-        while function_cursor.go_to_parent() {
-            if !function_cursor
-                .node()
-                .is_nonterminal_with_kind(NonterminalKind::FunctionDefinition)
-            {
+    let mut findings = Vec::new();
+    for rule in RULES {
+        let cursor = parse_output.create_tree_cursor();
+
+        // mapping of function identifier offset to a list of accessed members
+        let mut accesses = HashMap::<usize, Vec<String>>::new();
+        for m in cursor.query((rule.queries)()?) {
+            let Some((member_name, start_cursor)) = (rule.classify)(&m) else {
                 continue;
             };
-            if function_cursor.go_to_next_terminal_with_kind(TerminalKind::Identifier) {
-                let range = function_cursor.text_range();
-                let function_accesses = accesses.entry(range.start.utf8).or_default();
-                let function_name = function_cursor.node().unparse();
-                if function_accesses.contains(&member_name) {
-                    eprintln!(
-                        "Function `{function_name}` in {}:{} uses `{member_name}` more than once",
-                        path.to_string_lossy(),
-                        range.line().start,
-                    );
-                    break;
+
+            let mut function_cursor = start_cursor;
+            while function_cursor.go_to_parent() {
+                if !function_cursor
+                    .node()
+                    .is_nonterminal_with_kind(NonterminalKind::FunctionDefinition)
+                {
+                    continue;
+                };
+                if function_cursor.go_to_next_terminal_with_kind(TerminalKind::Identifier) {
+                    let range = function_cursor.text_range();
+                    let function_accesses = accesses.entry(range.start.utf8).or_default();
+                    let function_name = function_cursor.node().unparse();
+                    if function_accesses.contains(&member_name) {
+                        findings.push(duplicate_storage_read_finding(
+                            rule,
+                            path,
+                            &function_name,
+                            &member_name,
+                            &range,
+                        ));
+                        break;
+                    }
+                    function_accesses.push(member_name);
                 }
-                function_accesses.push(member_name);
+                break;
             }
-            break;
         }
     }
 
+    Ok(findings)
+}
+
+fn duplicate_storage_read_finding(
+    rule: &Rule,
+    path: &Path,
+    function_name: &str,
+    member_name: &str,
+    range: &TextRange,
+) -> Finding {
+    Finding {
+        rule_id: rule.id,
+        severity: rule.severity,
+        file: path.to_path_buf(),
+        byte_range: (range.start.utf8, range.end.utf8),
+        line: range.line().start,
+        column: range.column().start,
+        message: format!("Function `{function_name}` uses `{member_name}` more than once"),
+    }
+}
+
+fn report_human(findings: &[Finding]) {
+    for finding in findings {
+        eprintln!(
+            "[{rule_id}] {file}:{line}:{column}: {message}",
+            rule_id = finding.rule_id,
+            file = finding.file.to_string_lossy(),
+            line = finding.line,
+            column = finding.column,
+            message = finding.message,
+        );
+    }
+}
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+struct SarifDriver {
+    name: &'static str,
+    rules: Vec<SarifRuleDescriptor>,
+}
+
+#[derive(Serialize)]
+struct SarifRuleDescriptor {
+    id: &'static str,
+}
+
+#[derive(Serialize)]
+struct SarifResult {
+    #[serde(rename = "ruleId")]
+    rule_id: &'static str,
+    level: &'static str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct SarifLocation {
+    #[serde(rename = "physicalLocation")]
+    physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Serialize)]
+struct SarifPhysicalLocation {
+    #[serde(rename = "artifactLocation")]
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+struct SarifRegion {
+    #[serde(rename = "startLine")]
+    start_line: usize,
+    #[serde(rename = "startColumn")]
+    start_column: usize,
+    #[serde(rename = "byteOffset")]
+    byte_offset: usize,
+    #[serde(rename = "byteLength")]
+    byte_length: usize,
+}
+
+fn report_sarif(findings: &[Finding]) -> Result<()> {
+    let log = SarifLog {
+        schema: "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
+        version: "2.1.0",
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: "usdn-storage-linter",
+                    rules: RULES
+                        .iter()
+                        .map(|rule| SarifRuleDescriptor { id: rule.id })
+                        .collect(),
+                },
+            },
+            results: findings
+                .iter()
+                .map(|finding| SarifResult {
+                    rule_id: finding.rule_id,
+                    level: match finding.severity {
+                        Severity::Error => "error",
+                        Severity::Warning => "warning",
+                    },
+                    message: SarifMessage {
+                        text: finding.message.clone(),
+                    },
+                    locations: vec![SarifLocation {
+                        physical_location: SarifPhysicalLocation {
+                            artifact_location: SarifArtifactLocation {
+                                uri: finding.file.to_string_lossy().into_owned(),
+                            },
+                            region: SarifRegion {
+                                // slang's line and column are 0-based, SARIF's are 1-based
+                                start_line: finding.line + 1,
+                                start_column: finding.column + 1,
+                                byte_offset: finding.byte_range.0,
+                                byte_length: finding.byte_range.1 - finding.byte_range.0,
+                            },
+                        },
+                    }],
+                })
+                .collect(),
+        }],
+    };
+    println!("{}", serde_json::to_string_pretty(&log)?);
     Ok(())
 }
@@ -1,14 +1,19 @@
 use std::{
     ops::DivAssign,
-    time::{SystemTime, UNIX_EPOCH},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use alloy_primitives::{Bytes, FixedBytes, I256, U256};
+use alloy_primitives::{
+    aliases::{I192, U192},
+    keccak256, Address, Bytes, FixedBytes, I256, U256,
+};
 use alloy_sol_types::SolValue;
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::{Parser, Subcommand};
 use data_streams_report::report::Report;
 use hmac::{Hmac, Mac};
+use k256::ecdsa::{RecoveryId, Signature as RecoverableSignature, VerifyingKey};
 use rug::{
     float::Round,
     ops::{DivRounding, MulAssignRound, Pow},
@@ -16,6 +21,7 @@ use rug::{
 };
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
+use tungstenite::{client::IntoClientRequest, connect, Message};
 
 #[derive(Deserialize, Debug)]
 struct HermesResponse {
@@ -59,12 +65,16 @@ enum Commands {
         /// exponent
         #[arg(allow_hyphen_values = true, value_parser = parse_float)]
         value: Float,
+        #[command(flatten)]
+        cross_check: CrossCheckArgs,
     },
     /// ln(x) in WAD denomination
     LnWad {
         /// operand
         #[arg(allow_hyphen_values = true, value_parser = parse_float)]
         value: Float,
+        #[command(flatten)]
+        cross_check: CrossCheckArgs,
     },
     PowWad {
         /// Base
@@ -73,6 +83,8 @@ enum Commands {
         /// Exponent
         #[arg(value_parser = parse_float)]
         exp: Float,
+        #[command(flatten)]
+        cross_check: CrossCheckArgs,
     },
     /// ceil(lhs / rhs)
     DivUp {
@@ -80,6 +92,8 @@ enum Commands {
         lhs: Integer,
         /// RHS
         rhs: Integer,
+        #[command(flatten)]
+        cross_check: CrossCheckArgs,
     },
     /// Get price feed from Pyth hermes API
     PythPrice {
@@ -87,18 +101,25 @@ enum Commands {
         feed: String,
         /// The publish time
         publish_time: u64,
+        /// Verify the VAA guardian signatures before printing the result
+        #[arg(long)]
+        verify: bool,
     },
     /// Compare different total expo calculation implementations
     CalcExpo {
         start_price: Integer,
         liq_price: Integer,
         amount: Integer,
+        #[command(flatten)]
+        cross_check: CrossCheckArgs,
     },
     /// Compare different mint usdn calculation implementations
     CalcMintUsdnShares {
         amount: Integer,
         vault_balance: Integer,
         usdn_total_shares: Integer,
+        #[command(flatten)]
+        cross_check: CrossCheckArgs,
     },
     /// Compare different mint usdn calculation implementations (with vaultBalance equal to zero)
     CalcMintUsdnSharesVaultBalanceZero {
@@ -106,6 +127,8 @@ enum Commands {
         price: Integer,
         decimals: u32,
         usdn_divisor: Integer,
+        #[command(flatten)]
+        cross_check: CrossCheckArgs,
     },
     /// Get price from chainlink data streams api
     ChainlinkPrice {
@@ -113,32 +136,193 @@ enum Commands {
         feed_id: String,
         /// The price timestamp
         timestamp: u128,
+        /// Verify the report signatures before printing the result
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Stream prices from the chainlink data streams websocket API
+    ChainlinkStream {
+        /// The chainlink datastream ids to subscribe to
+        feed_ids: Vec<String>,
+    },
+    /// ABI-decode a chainlink data streams `full_report` blob into its typed fields
+    DecodeReport {
+        /// The `full_report` hex blob returned by the data streams API
+        full_report: String,
     },
 }
 
+/// Cross-checks a rust reference value against a deployed verifier contract. Provide both
+/// `--rpc` and `--verifier` to replay the same computation through an `eth_call` and compare.
+#[derive(clap::Args, Debug)]
+struct CrossCheckArgs {
+    /// JSON-RPC endpoint of a node with the verifier contract deployed
+    #[arg(long, requires = "verifier")]
+    rpc: Option<String>,
+    /// Address of the deployed verifier helper contract
+    #[arg(long, requires = "rpc")]
+    verifier: Option<Address>,
+    /// Maximum relative difference tolerated between the rust reference value and the
+    /// on-chain result, in WAD (1e18 == 100%), before exiting with an error
+    #[arg(long, default_value = "100000000000000")]
+    tolerance: Integer,
+}
+
+/// ABI envelope wrapping every chainlink data streams report, regardless of schema version.
+type ReportEnvelope = (
+    [FixedBytes<32>; 3],
+    Bytes,
+    Vec<FixedBytes<32>>,
+    Vec<FixedBytes<32>>,
+    FixedBytes<32>,
+);
+
+/// Fields of a v3 "crypto" report blob, in ABI order.
+type ReportV3Fields = (FixedBytes<32>, u32, u32, U192, U192, u32, I192, I192, I192);
+
+/// Fields of a v4 "RWA" report blob, in ABI order: the v3 fields plus a trailing market status.
+type ReportV4Fields = (
+    FixedBytes<32>,
+    u32,
+    u32,
+    U192,
+    U192,
+    u32,
+    I192,
+    I192,
+    I192,
+    u32,
+);
+
+const REPORT_SCHEMA_V3: u16 = 3;
+const REPORT_SCHEMA_V4: u16 = 4;
+
+/// A decoded chainlink data streams v3 "crypto" report.
+#[derive(Debug)]
+struct ReportV3 {
+    feed_id: FixedBytes<32>,
+    valid_from_timestamp: u32,
+    observations_timestamp: u32,
+    native_fee: U256,
+    link_fee: U256,
+    expires_at: u32,
+    price: I256,
+    bid: I256,
+    ask: I256,
+}
+
+impl ReportV3 {
+    fn abi_encode_params(&self) -> Vec<u8> {
+        (
+            self.feed_id,
+            self.valid_from_timestamp,
+            self.observations_timestamp,
+            self.native_fee,
+            self.link_fee,
+            self.expires_at,
+            self.price,
+            self.bid,
+            self.ask,
+        )
+            .abi_encode_params()
+    }
+}
+
+/// A decoded chainlink data streams v4 "RWA" report: the v3 layout plus a market status flag.
+#[derive(Debug)]
+struct ReportV4 {
+    base: ReportV3,
+    market_status: u32,
+}
+
+/// The chainlink data streams report schema versions this tool knows how to decode. New
+/// schema versions should be added here rather than branching ad-hoc on the version byte.
+#[derive(Debug)]
+enum DecodedReport {
+    V3(ReportV3),
+    V4(ReportV4),
+}
+
+impl DecodedReport {
+    fn abi_encode_params(&self) -> Vec<u8> {
+        match self {
+            DecodedReport::V3(report) => report.abi_encode_params(),
+            DecodedReport::V4(report) => (
+                report.base.feed_id,
+                report.base.valid_from_timestamp,
+                report.base.observations_timestamp,
+                report.base.native_fee,
+                report.base.link_fee,
+                report.base.expires_at,
+                report.base.price,
+                report.base.bid,
+                report.base.ask,
+                report.market_status,
+            )
+                .abi_encode_params(),
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     let wad: Integer = "1000000000000000000".parse().unwrap();
 
     match cli.command {
-        Commands::ExpWad { value } => {
+        Commands::ExpWad { value, cross_check } => {
+            let value_wad = value
+                .to_integer()
+                .ok_or_else(|| anyhow!("can't convert value to integer"))?;
             let mut value = value;
             value.div_assign(&wad);
             let mut res = value.exp();
             res.mul_assign_round(&wad, Round::Nearest);
             res.floor_mut();
-            print_float_i256_hex(res)?;
+            let res = res
+                .to_integer()
+                .ok_or_else(|| anyhow!("can't convert result to integer"))?;
+            run_cross_check(
+                &cross_check,
+                "expWad(int256)",
+                (encode_i256(&value_wad)?,).abi_encode_params(),
+                true,
+                &res,
+            )?;
+            print_int_i256_hex(res)?;
         }
-        Commands::LnWad { value } => {
+        Commands::LnWad { value, cross_check } => {
+            let value_wad = value
+                .to_integer()
+                .ok_or_else(|| anyhow!("can't convert value to integer"))?;
             let mut value = value;
             value.div_assign(&wad);
             let mut res = value.ln();
             res.mul_assign_round(&wad, Round::Nearest);
             res.round_mut();
-            print_float_i256_hex(res)?;
+            let res = res
+                .to_integer()
+                .ok_or_else(|| anyhow!("can't convert result to integer"))?;
+            run_cross_check(
+                &cross_check,
+                "lnWad(int256)",
+                (encode_i256(&value_wad)?,).abi_encode_params(),
+                true,
+                &res,
+            )?;
+            print_int_i256_hex(res)?;
         }
-        Commands::PowWad { base, exp } => {
+        Commands::PowWad {
+            base,
+            exp,
+            cross_check,
+        } => {
+            let base_wad = base
+                .to_integer()
+                .ok_or_else(|| anyhow!("can't convert base to integer"))?;
+            let exp_wad = exp
+                .to_integer()
+                .ok_or_else(|| anyhow!("can't convert exp to integer"))?;
             let mut base = base;
             base.div_assign(&wad);
             let mut exp = exp;
@@ -146,13 +330,40 @@ fn main() -> Result<()> {
             let mut res = base.pow(exp);
             res.mul_assign_round(&wad, Round::Nearest);
             res.round_mut();
-            print_float_i256_hex(res)?;
+            let res = res
+                .to_integer()
+                .ok_or_else(|| anyhow!("can't convert result to integer"))?;
+            run_cross_check(
+                &cross_check,
+                "powWad(int256,int256)",
+                (encode_i256(&base_wad)?, encode_i256(&exp_wad)?).abi_encode_params(),
+                true,
+                &res,
+            )?;
+            print_int_i256_hex(res)?;
         }
-        Commands::DivUp { lhs, rhs } => {
+        Commands::DivUp {
+            lhs,
+            rhs,
+            cross_check,
+        } => {
+            let lhs_for_check = lhs.clone();
+            let rhs_for_check = rhs.clone();
             let res = lhs.div_ceil(rhs);
+            run_cross_check(
+                &cross_check,
+                "divUp(uint256,uint256)",
+                (encode_u256(&lhs_for_check)?, encode_u256(&rhs_for_check)?).abi_encode_params(),
+                false,
+                &res,
+            )?;
             print_int_u256_hex(res)?;
         }
-        Commands::PythPrice { feed, publish_time } => {
+        Commands::PythPrice {
+            feed,
+            publish_time,
+            verify,
+        } => {
             let mut hermes_api_url = std::env::var("HERMES_RA2_NODE_URL")
                 .context("getting HERMES_RA2_NODE_URL env variable")?;
             // add / to the end of the url if it's not there
@@ -165,25 +376,60 @@ fn main() -> Result<()> {
             );
             let response = ureq::get(&request_url).call()?;
             let price: HermesResponse = response.into_json()?;
+            if verify {
+                verify_pyth_vaa(&price)?;
+            }
             print_pyth_response(price)?;
         }
         Commands::CalcExpo {
             start_price,
             liq_price,
             amount,
+            cross_check,
         } => {
+            let start_price_for_check = start_price.clone();
+            let liq_price_for_check = liq_price.clone();
+            let amount_for_check = amount.clone();
             let price_diff = &start_price - liq_price;
             let numerator = amount * start_price;
             let total_mint = numerator / price_diff;
+            run_cross_check(
+                &cross_check,
+                "calcExpo(uint256,uint256,uint256)",
+                (
+                    encode_u256(&start_price_for_check)?,
+                    encode_u256(&liq_price_for_check)?,
+                    encode_u256(&amount_for_check)?,
+                )
+                    .abi_encode_params(),
+                false,
+                &total_mint,
+            )?;
             print_int_u256_hex(total_mint)?;
         }
         Commands::CalcMintUsdnShares {
             amount,
             vault_balance,
             usdn_total_shares,
+            cross_check,
         } => {
+            let amount_for_check = amount.clone();
+            let vault_balance_for_check = vault_balance.clone();
+            let usdn_total_shares_for_check = usdn_total_shares.clone();
             let numerator = amount * usdn_total_shares;
             let total_mint = numerator / vault_balance;
+            run_cross_check(
+                &cross_check,
+                "calcMintUsdnShares(uint256,uint256,uint256)",
+                (
+                    encode_u256(&amount_for_check)?,
+                    encode_u256(&vault_balance_for_check)?,
+                    encode_u256(&usdn_total_shares_for_check)?,
+                )
+                    .abi_encode_params(),
+                false,
+                &total_mint,
+            )?;
             print_int_u256_hex(total_mint)?;
         }
         Commands::CalcMintUsdnSharesVaultBalanceZero {
@@ -191,13 +437,34 @@ fn main() -> Result<()> {
             price,
             decimals,
             usdn_divisor,
+            cross_check,
         } => {
+            let amount_for_check = amount.clone();
+            let price_for_check = price.clone();
+            let usdn_divisor_for_check = usdn_divisor.clone();
             let numerator = amount * price;
             let total_mint = numerator / 10u128.pow(decimals);
             let total_mint_shares = total_mint * usdn_divisor;
+            run_cross_check(
+                &cross_check,
+                "calcMintUsdnSharesVaultBalanceZero(uint256,uint256,uint32,uint256)",
+                (
+                    encode_u256(&amount_for_check)?,
+                    encode_u256(&price_for_check)?,
+                    decimals,
+                    encode_u256(&usdn_divisor_for_check)?,
+                )
+                    .abi_encode_params(),
+                false,
+                &total_mint_shares,
+            )?;
             print_int_u256_hex(total_mint_shares)?;
         }
-        Commands::ChainlinkPrice { feed_id, timestamp } => {
+        Commands::ChainlinkPrice {
+            feed_id,
+            timestamp,
+            verify,
+        } => {
             let chainlink_low_latency_api_key = std::env::var("CHAINLINK_DATA_STREAMS_API_KEY")
                 .context("getting CHAINLINK_DATA_STREAMS_API_KEY env variable")?;
 
@@ -231,17 +498,361 @@ fn main() -> Result<()> {
             let report_response: ReportResponse = response.into_json()?;
             let report: Report = report_response.report;
 
+            if verify {
+                verify_chainlink_report(&report.full_report)?;
+            }
+
             print!("{}", report.full_report);
         }
+        Commands::ChainlinkStream { feed_ids } => {
+            stream_chainlink_reports(feed_ids)?;
+        }
+        Commands::DecodeReport { full_report } => {
+            let decoded = decode_chainlink_report(&full_report)?;
+            let bytes: Bytes = decoded.abi_encode_params().into();
+            print!("{bytes}");
+        }
     }
     Ok(())
 }
 
-fn print_float_i256_hex(x: Float) -> Result<()> {
-    let x_wad = x
-        .to_integer()
-        .ok_or_else(|| anyhow!("can't convert to integer"))?;
-    let x_hex: I256 = x_wad.to_string().parse()?;
+/// ABI-decodes a chainlink data streams `full_report` blob, dispatching on the report
+/// schema version taken from the high two bytes of the feed id.
+fn decode_chainlink_report(full_report: &str) -> Result<DecodedReport> {
+    let full_report_bytes = const_hex::decode(full_report)?;
+    let (_report_context, report_blob, _rs, _ss, _raw_vs) =
+        ReportEnvelope::abi_decode_params(&full_report_bytes, true)?;
+
+    // the feed id is the first field of `reportBlob`, not of the OCR signing context
+    let (feed_id,) = <(FixedBytes<32>,)>::abi_decode_params(&report_blob, true)?;
+    let schema_version = u16::from_be_bytes([feed_id[0], feed_id[1]]);
+
+    match schema_version {
+        REPORT_SCHEMA_V3 => {
+            let (
+                feed_id,
+                valid_from_timestamp,
+                observations_timestamp,
+                native_fee,
+                link_fee,
+                expires_at,
+                price,
+                bid,
+                ask,
+            ) = ReportV3Fields::abi_decode_params(&report_blob, true)?;
+            Ok(DecodedReport::V3(ReportV3 {
+                feed_id,
+                valid_from_timestamp,
+                observations_timestamp,
+                native_fee: widen_u192(native_fee),
+                link_fee: widen_u192(link_fee),
+                expires_at,
+                price: widen_i192(price),
+                bid: widen_i192(bid),
+                ask: widen_i192(ask),
+            }))
+        }
+        REPORT_SCHEMA_V4 => {
+            let (
+                feed_id,
+                valid_from_timestamp,
+                observations_timestamp,
+                native_fee,
+                link_fee,
+                expires_at,
+                price,
+                bid,
+                ask,
+                market_status,
+            ) = ReportV4Fields::abi_decode_params(&report_blob, true)?;
+            Ok(DecodedReport::V4(ReportV4 {
+                base: ReportV3 {
+                    feed_id,
+                    valid_from_timestamp,
+                    observations_timestamp,
+                    native_fee: widen_u192(native_fee),
+                    link_fee: widen_u192(link_fee),
+                    expires_at,
+                    price: widen_i192(price),
+                    bid: widen_i192(bid),
+                    ask: widen_i192(ask),
+                },
+                market_status,
+            }))
+        }
+        other => Err(anyhow!(
+            "unknown chainlink report schema version {other} in feed id {feed_id}"
+        )),
+    }
+}
+
+/// Zero-extends a `uint192` into a `U256`.
+fn widen_u192(value: U192) -> U256 {
+    let bytes = value.to_be_bytes::<24>();
+    let mut extended = [0u8; 32];
+    extended[8..].copy_from_slice(&bytes);
+    U256::from_be_bytes(extended)
+}
+
+/// Sign-extends an `int192` into an `I256`.
+fn widen_i192(value: I192) -> I256 {
+    let bytes = value.to_be_bytes::<24>();
+    let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0x00 };
+    let mut extended = [fill; 32];
+    extended[8..].copy_from_slice(&bytes);
+    I256::from_be_bytes(extended)
+}
+
+/// Recovers the address that produced `(r, s, v)` over `digest`, the same way the
+/// contracts' `ecrecover` precompile would.
+fn ecrecover(
+    digest: FixedBytes<32>,
+    r: FixedBytes<32>,
+    s: FixedBytes<32>,
+    v: u8,
+) -> Result<Address> {
+    let signature = RecoverableSignature::from_scalars(*r, *s)
+        .map_err(|e| anyhow!("invalid signature scalars: {e}"))?;
+    // normalize both the ethereum-style {27, 28} and the raw {0, 1} conventions
+    let recovery_byte = if v >= 27 { v - 27 } else { v };
+    let recovery_id =
+        RecoveryId::from_byte(recovery_byte).ok_or_else(|| anyhow!("invalid recovery id {v}"))?;
+    let verifying_key =
+        VerifyingKey::recover_from_prehash(digest.as_slice(), &signature, recovery_id)
+            .map_err(|e| anyhow!("signature recovery failed: {e}"))?;
+    let encoded_point = verifying_key.to_encoded_point(false);
+    let hash = keccak256(&encoded_point.as_bytes()[1..]);
+    Ok(Address::from_slice(&hash[12..]))
+}
+
+/// Reads a comma-separated list of addresses from the environment.
+fn parse_address_list_env(var: &str) -> Result<Vec<Address>> {
+    std::env::var(var)
+        .with_context(|| format!("getting {var} env variable"))?
+        .split(',')
+        .map(|s| s.trim().parse::<Address>().map_err(|e| anyhow!(e)))
+        .collect()
+}
+
+/// Checks that at least `quorum` distinct `recovered` addresses belong to `allowed`.
+fn ensure_quorum(recovered: &[Address], allowed: &[Address], quorum: usize) -> Result<()> {
+    let mut matched = std::collections::HashSet::new();
+    for address in recovered {
+        if allowed.contains(address) {
+            matched.insert(*address);
+        }
+    }
+    if matched.len() < quorum {
+        bail!(
+            "signature quorum not met: {} of {quorum} required signers recovered",
+            matched.len()
+        );
+    }
+    Ok(())
+}
+
+/// Reconstructs the chainlink data streams signed digest and `ecrecover`s every signature in
+/// the report, checking that a distinct quorum of `CHAINLINK_DATA_STREAMS_SIGNERS` signed it.
+fn verify_chainlink_report(full_report: &str) -> Result<()> {
+    let full_report_bytes = const_hex::decode(full_report)?;
+    let (report_context, report_blob, rs, ss, raw_vs) =
+        ReportEnvelope::abi_decode_params(&full_report_bytes, true)?;
+
+    if rs.len() != ss.len() {
+        bail!("mismatched rs/ss signature array lengths");
+    }
+
+    let hashed_report = keccak256(report_blob.as_ref());
+    let mut packed = Vec::with_capacity(32 * 4);
+    packed.extend_from_slice(hashed_report.as_slice());
+    for word in &report_context {
+        packed.extend_from_slice(word.as_slice());
+    }
+    let digest = keccak256(&packed);
+
+    let recovered = rs
+        .iter()
+        .zip(ss.iter())
+        .enumerate()
+        .map(|(i, (r, s))| {
+            let v = *raw_vs
+                .get(i)
+                .ok_or_else(|| anyhow!("report has more signatures than `rawVs` bytes"))?;
+            ecrecover(digest, *r, *s, v)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let signers = parse_address_list_env("CHAINLINK_DATA_STREAMS_SIGNERS")?;
+    let quorum: usize = std::env::var("CHAINLINK_DATA_STREAMS_QUORUM")
+        .context("getting CHAINLINK_DATA_STREAMS_QUORUM env variable")?
+        .parse()
+        .context("parsing CHAINLINK_DATA_STREAMS_QUORUM as a number")?;
+
+    ensure_quorum(&recovered, &signers, quorum)
+}
+
+/// A single guardian signature from a Pyth VAA.
+struct GuardianSignature {
+    r: FixedBytes<32>,
+    s: FixedBytes<32>,
+    v: u8,
+}
+
+/// The fields of a Wormhole VAA relevant to signature verification.
+struct Vaa {
+    guardian_set_index: u32,
+    signatures: Vec<GuardianSignature>,
+    body: Vec<u8>,
+}
+
+/// Parses a Wormhole VAA: `version (1) | guardian_set_index (4) | signature_count (1) |
+/// signatures (66 each) | body`.
+fn parse_vaa(data: &[u8]) -> Result<Vaa> {
+    let mut offset = 1; // skip the version byte
+    let guardian_set_index = u32::from_be_bytes(
+        data.get(offset..offset + 4)
+            .ok_or_else(|| anyhow!("VAA truncated before guardian set index"))?
+            .try_into()?,
+    );
+    offset += 4;
+    let signature_count = *data
+        .get(offset)
+        .ok_or_else(|| anyhow!("VAA truncated before signature count"))?
+        as usize;
+    offset += 1;
+
+    let mut signatures = Vec::with_capacity(signature_count);
+    for _ in 0..signature_count {
+        let signature_bytes = data
+            .get(offset..offset + 66)
+            .ok_or_else(|| anyhow!("VAA truncated in signature list"))?;
+        let r = FixedBytes::<32>::from_slice(&signature_bytes[1..33]);
+        let s = FixedBytes::<32>::from_slice(&signature_bytes[33..65]);
+        let v = signature_bytes[65];
+        signatures.push(GuardianSignature { r, s, v });
+        offset += 66;
+    }
+
+    let body = data
+        .get(offset..)
+        .ok_or_else(|| anyhow!("VAA truncated before body"))?
+        .to_vec();
+
+    Ok(Vaa {
+        guardian_set_index,
+        signatures,
+        body,
+    })
+}
+
+/// `ecrecover`s every guardian signature in the VAA over `keccak256(keccak256(body))`,
+/// requiring at least 2/3+1 of `PYTH_GUARDIAN_SET` to have signed it.
+fn verify_pyth_vaa(response: &HermesResponse) -> Result<()> {
+    let vaa_hex = response
+        .binary
+        .data
+        .first()
+        .ok_or_else(|| anyhow!("no VAA in pyth response"))?;
+    let decoded_vaa = const_hex::decode(vaa_hex)?;
+    let vaa = parse_vaa(&decoded_vaa)?;
+
+    let digest = keccak256(keccak256(&vaa.body));
+    let recovered = vaa
+        .signatures
+        .iter()
+        .map(|sig| ecrecover(digest, sig.r, sig.s, sig.v))
+        .collect::<Result<Vec<_>>>()?;
+
+    let guardians = parse_address_list_env("PYTH_GUARDIAN_SET")?;
+    let quorum = guardians.len() * 2 / 3 + 1;
+    ensure_quorum(&recovered, &guardians, quorum).with_context(|| {
+        format!(
+            "verifying VAA signed by guardian set {}",
+            vaa.guardian_set_index
+        )
+    })
+}
+
+/// Opens the chainlink data streams websocket endpoint and prints each decoded report to
+/// stdout as a newline-delimited stream until interrupted, reconnecting with exponential
+/// backoff whenever the socket drops.
+fn stream_chainlink_reports(feed_ids: Vec<String>) -> Result<()> {
+    let chainlink_low_latency_api_key = std::env::var("CHAINLINK_DATA_STREAMS_API_KEY")
+        .context("getting CHAINLINK_DATA_STREAMS_API_KEY env variable")?;
+    let chainlink_user_secret = std::env::var("CHAINLINK_DATA_STREAMS_API_SECRET")
+        .context("getting CHAINLINK_DATA_STREAMS_API_SECRET env variable")?;
+    let chainlink_ws_url = std::env::var("CHAINLINK_DATA_STREAMS_WS_URL")
+        .context("getting CHAINLINK_DATA_STREAMS_WS_URL env variable")?;
+
+    let path = format!("/api/v1/ws?feedIDs={}", feed_ids.join(","));
+
+    const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        let request_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Error: Timestamp in the past")
+            .as_millis();
+
+        let hmac_string = generate_hmac(
+            "GET",
+            &path,
+            b"",
+            &chainlink_low_latency_api_key,
+            request_timestamp,
+            &chainlink_user_secret,
+        )?;
+
+        let mut request = format!("{chainlink_ws_url}{path}").into_client_request()?;
+        let headers = request.headers_mut();
+        headers.insert("Authorization", chainlink_low_latency_api_key.parse()?);
+        headers.insert(
+            "X-Authorization-Timestamp",
+            request_timestamp.to_string().parse()?,
+        );
+        headers.insert("X-Authorization-Signature-SHA256", hmac_string.parse()?);
+
+        match connect(request) {
+            Ok((mut socket, _response)) => {
+                backoff = INITIAL_BACKOFF;
+                loop {
+                    match socket.read() {
+                        Ok(Message::Text(text)) => print_stream_report(text.as_bytes()),
+                        Ok(Message::Binary(data)) => print_stream_report(&data),
+                        Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => {}
+                        Ok(Message::Close(_)) => {
+                            eprintln!("chainlink data streams websocket closed by server");
+                            break;
+                        }
+                        Err(e) => {
+                            eprintln!("chainlink data streams websocket read error: {e:?}");
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("failed to connect to chainlink data streams websocket: {e:?}");
+            }
+        }
+
+        eprintln!("reconnecting in {backoff:?}");
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+fn print_stream_report(data: &[u8]) {
+    match serde_json::from_slice::<ReportResponse>(data) {
+        Ok(report_response) => println!("{}", report_response.report.full_report),
+        Err(e) => eprintln!("failed to decode chainlink report: {e:?}"),
+    }
+}
+
+fn print_int_i256_hex(x: Integer) -> Result<()> {
+    let x_hex: I256 = x.to_string().parse()?;
     let bytes: [u8; 32] = x_hex.to_be_bytes();
     let x_bytes: FixedBytes<32> = bytes.into();
     print!("{x_bytes}");
@@ -256,6 +867,89 @@ fn print_int_u256_hex(x: Integer) -> Result<()> {
     Ok(())
 }
 
+fn encode_i256(x: &Integer) -> Result<I256> {
+    Ok(x.to_string().parse()?)
+}
+
+fn encode_u256(x: &Integer) -> Result<U256> {
+    Ok(x.to_string().parse()?)
+}
+
+/// Replays `signature(call_args)` against the deployed verifier contract via `eth_call` and
+/// compares the result to the rust `reference` value, reporting the absolute and relative
+/// difference. Does nothing unless both `--rpc` and `--verifier` were passed. Exits with an
+/// error if the relative difference exceeds `--tolerance`.
+fn run_cross_check(
+    cross_check: &CrossCheckArgs,
+    signature: &str,
+    call_args: Vec<u8>,
+    is_signed: bool,
+    reference: &Integer,
+) -> Result<()> {
+    let (Some(rpc_url), Some(verifier)) = (cross_check.rpc.as_deref(), cross_check.verifier) else {
+        return Ok(());
+    };
+
+    let signature_hash = keccak256(signature.as_bytes());
+    let mut calldata = signature_hash[..4].to_vec();
+    calldata.extend_from_slice(&call_args);
+    let calldata: Bytes = calldata.into();
+
+    let response = ureq::post(rpc_url)
+        .send_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_call",
+            "params": [{"to": verifier, "data": calldata.to_string()}, "latest"],
+        }))?
+        .into_json::<serde_json::Value>()?;
+    let result_hex = response
+        .get("result")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("no result in eth_call response: {response}"))?;
+    let result_bytes = const_hex::decode(result_hex)?;
+
+    let onchain: Integer = if is_signed {
+        I256::try_from_be_slice(&result_bytes)
+            .ok_or_else(|| anyhow!("eth_call result is not a valid int256"))?
+            .to_string()
+            .parse()?
+    } else {
+        U256::try_from_be_slice(&result_bytes)
+            .ok_or_else(|| anyhow!("eth_call result is not a valid uint256"))?
+            .to_string()
+            .parse()?
+    };
+
+    let abs_diff = (reference.clone() - onchain.clone()).abs();
+    let wad: Integer = "1000000000000000000".parse().unwrap();
+    let relative_diff_wad = if onchain == 0 {
+        if abs_diff == 0 {
+            Integer::from(0)
+        } else {
+            // no onchain value to compare against: treat as maximally divergent
+            cross_check.tolerance.clone() + 1
+        }
+    } else {
+        (abs_diff.clone() * wad) / onchain.clone().abs()
+    };
+
+    eprintln!(
+        "cross-check {signature} against {verifier}: rust={reference} onchain={onchain} \
+         abs_diff={abs_diff} relative_diff_wad={relative_diff_wad}"
+    );
+
+    if relative_diff_wad > cross_check.tolerance {
+        bail!(
+            "cross-check failed for {signature}: relative difference {relative_diff_wad} \
+             exceeds tolerance {tolerance}",
+            tolerance = cross_check.tolerance,
+        );
+    }
+
+    Ok(())
+}
+
 fn print_pyth_response(response: HermesResponse) -> Result<()> {
     let parsed = response
         .parsed
@@ -333,3 +1027,103 @@ fn generate_hmac(
 
     Ok(user_hmac)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_quorum_table() {
+        let a = Address::repeat_byte(0x11);
+        let b = Address::repeat_byte(0x22);
+        let allowed = vec![a, b];
+
+        struct Case {
+            name: &'static str,
+            recovered: Vec<Address>,
+            quorum: usize,
+            expect_ok: bool,
+        }
+        let cases = [
+            Case {
+                name: "exact quorum",
+                recovered: vec![a, b],
+                quorum: 2,
+                expect_ok: true,
+            },
+            Case {
+                name: "one short",
+                recovered: vec![a],
+                quorum: 2,
+                expect_ok: false,
+            },
+            Case {
+                name: "duplicate signer doesn't double count",
+                recovered: vec![a, a],
+                quorum: 2,
+                expect_ok: false,
+            },
+        ];
+
+        for case in cases {
+            let result = ensure_quorum(&case.recovered, &allowed, case.quorum);
+            assert_eq!(result.is_ok(), case.expect_ok, "case `{}`", case.name);
+        }
+    }
+
+    #[test]
+    fn parse_vaa_rejects_truncated_buffers() {
+        struct Case {
+            name: &'static str,
+            data: Vec<u8>,
+        }
+        let cases = [
+            Case {
+                name: "truncated before guardian set index",
+                data: vec![1, 0, 0],
+            },
+            Case {
+                name: "truncated before signature count",
+                data: {
+                    let mut data = vec![1u8];
+                    data.extend_from_slice(&0u32.to_be_bytes());
+                    data
+                },
+            },
+            Case {
+                name: "truncated in signature list",
+                data: {
+                    let mut data = vec![1u8];
+                    data.extend_from_slice(&0u32.to_be_bytes());
+                    data.push(1); // claims one signature
+                    data.extend_from_slice(&[0u8; 10]); // far fewer than the 66 bytes required
+                    data
+                },
+            },
+        ];
+
+        for case in cases {
+            assert!(parse_vaa(&case.data).is_err(), "case `{}`", case.name);
+        }
+    }
+
+    #[test]
+    fn parse_vaa_parses_a_well_formed_vaa() {
+        let mut data = vec![1u8]; // version byte, ignored
+        data.extend_from_slice(&7u32.to_be_bytes()); // guardian_set_index
+        data.push(1); // signature_count
+        data.push(3); // guardian index, not modeled by `GuardianSignature`
+        data.extend_from_slice(&[0xaa; 32]); // r
+        data.extend_from_slice(&[0xbb; 32]); // s
+        data.push(27); // v
+        data.extend_from_slice(b"body bytes");
+
+        let vaa = parse_vaa(&data).expect("well-formed VAA should parse");
+        assert_eq!(vaa.guardian_set_index, 7);
+        assert_eq!(vaa.signatures.len(), 1);
+        assert_eq!(vaa.signatures[0].r, FixedBytes::<32>::from_slice(&[0xaa; 32]));
+        assert_eq!(vaa.signatures[0].s, FixedBytes::<32>::from_slice(&[0xbb; 32]));
+        assert_eq!(vaa.signatures[0].v, 27);
+        assert_eq!(vaa.body, b"body bytes");
+    }
+}